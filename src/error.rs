@@ -0,0 +1,58 @@
+use std::{fmt, time::Duration};
+
+/// Errors that can occur when talking to a Nominatim server.
+#[derive(Debug)]
+pub enum Error {
+    /// The request failed at the transport level (DNS, TCP, TLS, timeout, ...).
+    Http(reqwest::Error),
+    /// The server's response body could not be deserialized.
+    Json(reqwest::Error),
+    /// The [`IdentificationMethod`](crate::IdentificationMethod) or
+    /// [`SearchParams::accept_language`](crate::SearchParams::accept_language) produced a
+    /// value that isn't a valid HTTP header name or value.
+    InvalidHeader(String),
+    /// The server had no results for the query.
+    EmptyResults,
+    /// The server responded `429 Too Many Requests`. Carries how long to wait before
+    /// retrying, taken from the response's `Retry-After` header when present.
+    RateLimited { retry_after: Duration },
+    /// The offline database configured via
+    /// [`Client::with_offline_db`](crate::Client::with_offline_db) could not be opened
+    /// or queried. Only constructed when the `offline` feature is enabled.
+    #[cfg(feature = "offline")]
+    Offline(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Http(err) => write!(f, "request to nominatim server failed: {err}"),
+            Error::Json(err) => write!(f, "failed to parse nominatim response: {err}"),
+            Error::InvalidHeader(value) => write!(f, "invalid nominatim request header: {value}"),
+            Error::EmptyResults => write!(f, "nominatim server returned no results"),
+            Error::RateLimited { retry_after } => write!(
+                f,
+                "rate limited by nominatim server, retry after {retry_after:?}"
+            ),
+            #[cfg(feature = "offline")]
+            Error::Offline(message) => write!(f, "offline database error: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Http(err) | Error::Json(err) => Some(err),
+            Error::InvalidHeader(_) | Error::EmptyResults | Error::RateLimited { .. } => None,
+            #[cfg(feature = "offline")]
+            Error::Offline(_) => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Error::Http(err)
+    }
+}
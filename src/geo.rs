@@ -0,0 +1,152 @@
+//! Conversions from [`Place`] into [`geo_types`] geometries and GeoJSON
+//! [`FeatureCollection`]s, gated behind the `geo` feature.
+
+use std::fmt;
+
+use geo_types::{Geometry, LineString, MultiPolygon, Point, Polygon};
+use geojson::{Feature, FeatureCollection, JsonObject, JsonValue};
+
+use crate::{Coordinates, Place};
+
+/// A [`Place`] had no `geojson` field and no parseable `lat`/`lon` to fall back to.
+/// In practice this only happens for a hand-built [`Place`] with malformed
+/// coordinates, since Nominatim always sets `lat`/`lon`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoGeometryError;
+
+impl fmt::Display for NoGeometryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "place has no geojson geometry and no parseable lat/lon to convert")
+    }
+}
+
+impl std::error::Error for NoGeometryError {}
+
+fn ring_to_line_string(ring: &[[f64; 2]]) -> LineString<f64> {
+    LineString::from(ring.iter().map(|[lon, lat]| (*lon, *lat)).collect::<Vec<_>>())
+}
+
+fn rings_to_polygon(rings: &[Vec<[f64; 2]>]) -> Polygon<f64> {
+    let mut rings = rings.iter();
+    let exterior = rings
+        .next()
+        .map(|ring| ring_to_line_string(ring))
+        .unwrap_or_else(|| LineString::new(Vec::new()));
+    let interiors = rings.map(|ring| ring_to_line_string(ring)).collect();
+
+    Polygon::new(exterior, interiors)
+}
+
+impl From<&Coordinates> for Geometry<f64> {
+    fn from(coordinates: &Coordinates) -> Self {
+        match coordinates {
+            Coordinates::Point([lon, lat]) => Geometry::Point(Point::new(*lon, *lat)),
+            Coordinates::LineString(points) => Geometry::LineString(ring_to_line_string(points)),
+            Coordinates::Polygon(rings) => Geometry::Polygon(rings_to_polygon(rings)),
+            Coordinates::MultiPolygon(polygons) => Geometry::MultiPolygon(MultiPolygon::new(
+                polygons.iter().map(|rings| rings_to_polygon(rings)).collect(),
+            )),
+        }
+    }
+}
+
+/// Convert a [`Place`] to a [`Geometry`], preferring its `geojson` field (only
+/// populated when the request used [`GeometryFormat::GeoJson`](crate::GeometryFormat))
+/// and otherwise falling back to a [`Geometry::Point`] built from `lat`/`lon`, which
+/// every [`Place`] returned by Nominatim has.
+impl TryFrom<&Place> for Geometry<f64> {
+    type Error = NoGeometryError;
+
+    fn try_from(place: &Place) -> Result<Self, Self::Error> {
+        if let Some(geojson) = &place.geojson {
+            return Ok(Geometry::from(&geojson.coordinates));
+        }
+
+        let lat: f64 = place.lat.parse().map_err(|_| NoGeometryError)?;
+        let lon: f64 = place.lon.parse().map_err(|_| NoGeometryError)?;
+
+        Ok(Geometry::Point(Point::new(lon, lat)))
+    }
+}
+
+/// Flatten a [`Place`] into a GeoJSON [`Feature`], carrying `display_name`,
+/// `osm_type`/`osm_id`, `class`/`type`, `importance`, and the `city`, `state`,
+/// `postcode`, `country` and `country_code` of `address` (as `address.*` keys) as
+/// properties. Other `address` fields are not included.
+///
+/// Returns [`NoGeometryError`] if the place has neither a `geojson` geometry nor a
+/// parseable `lat`/`lon`.
+impl TryFrom<&Place> for Feature {
+    type Error = NoGeometryError;
+
+    fn try_from(place: &Place) -> Result<Self, Self::Error> {
+        let geometry: Geometry<f64> = place.try_into()?;
+
+        let mut properties = JsonObject::new();
+        properties.insert(
+            "display_name".to_string(),
+            JsonValue::from(place.display_name.clone()),
+        );
+        properties.insert("osm_type".to_string(), JsonValue::from(place.osm_type.clone()));
+        properties.insert("osm_id".to_string(), JsonValue::from(place.osm_id));
+
+        if let Some(class) = &place.class {
+            properties.insert("class".to_string(), JsonValue::from(class.clone()));
+        }
+
+        if let Some(place_type) = &place._type {
+            properties.insert("type".to_string(), JsonValue::from(place_type.clone()));
+        }
+
+        if let Some(importance) = place.importance {
+            properties.insert("importance".to_string(), JsonValue::from(importance));
+        }
+
+        if let Some(address) = &place.address {
+            if let Some(city) = &address.city {
+                properties.insert("address.city".to_string(), JsonValue::from(city.clone()));
+            }
+            if let Some(state) = &address.state {
+                properties.insert("address.state".to_string(), JsonValue::from(state.clone()));
+            }
+            if let Some(postcode) = &address.postcode {
+                properties.insert("address.postcode".to_string(), JsonValue::from(postcode.clone()));
+            }
+            if let Some(country) = &address.country {
+                properties.insert("address.country".to_string(), JsonValue::from(country.clone()));
+            }
+            if let Some(country_code) = &address.country_code {
+                properties.insert(
+                    "address.country_code".to_string(),
+                    JsonValue::from(country_code.clone()),
+                );
+            }
+        }
+
+        Ok(Feature {
+            bbox: None,
+            geometry: Some((&geometry).into()),
+            id: None,
+            properties: Some(properties),
+            foreign_members: None,
+        })
+    }
+}
+
+/// A borrowed slice of [`Place`]s, wrapped so it can implement the foreign
+/// [`FeatureCollection`] conversion despite Rust's orphan rules.
+#[derive(Debug, Clone, Copy)]
+pub struct Places<'a>(pub &'a [Place]);
+
+/// Convert [`Place`]s into a GeoJSON [`FeatureCollection`], falling back to a `Point`
+/// geometry for places with no `geojson` field, and silently skipping the rare place
+/// whose `lat`/`lon` can't even be parsed (see [`NoGeometryError`]).
+impl From<Places<'_>> for FeatureCollection {
+    fn from(places: Places<'_>) -> Self {
+        FeatureCollection {
+            bbox: None,
+            features: places.0.iter().filter_map(|place| place.try_into().ok()).collect(),
+            foreign_members: None,
+        }
+    }
+}
@@ -0,0 +1,30 @@
+/// The geometry format Nominatim should include alongside each result.
+///
+/// Selectable on [`Client`](crate::Client) via
+/// [`Client::set_geometry_format`](crate::Client::set_geometry_format) as a default for
+/// every place-returning call, or overridden per search via
+/// [`SearchParams::geometry_format`](crate::SearchParams::geometry_format).
+///
+/// Defaults to [`GeometryFormat::None`], since polygon geometry is the heaviest part
+/// of a Nominatim response and most callers don't need it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum GeometryFormat {
+    #[default]
+    None,
+    GeoJson,
+    Kml,
+    Svg,
+    Text,
+}
+
+impl GeometryFormat {
+    pub(crate) fn query_param(&self) -> Option<&'static str> {
+        match self {
+            GeometryFormat::None => None,
+            GeometryFormat::GeoJson => Some("polygon_geojson=1"),
+            GeometryFormat::Kml => Some("polygon_kml=1"),
+            GeometryFormat::Svg => Some("polygon_svg=1"),
+            GeometryFormat::Text => Some("polygon_text=1"),
+        }
+    }
+}
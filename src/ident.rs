@@ -0,0 +1,29 @@
+/// How a [`Client`](crate::Client) identifies itself to the Nominatim server.
+///
+/// The [usage policy](https://operations.osmfoundation.org/policies/nominatim/) requires
+/// every application to identify itself, either through the `User-Agent` header or
+/// through a valid `Referer` header.
+#[derive(Debug, Clone)]
+pub enum IdentificationMethod {
+    /// Identify via the `User-Agent` header.
+    UserAgent(String),
+    /// Identify via the `Referer` header.
+    Referer(String),
+}
+
+impl IdentificationMethod {
+    pub(crate) fn header(&self) -> String {
+        match self {
+            IdentificationMethod::UserAgent(_) => "User-Agent".to_string(),
+            IdentificationMethod::Referer(_) => "Referer".to_string(),
+        }
+    }
+
+    pub(crate) fn value(&self) -> String {
+        match self {
+            IdentificationMethod::UserAgent(value) | IdentificationMethod::Referer(value) => {
+                value.clone()
+            }
+        }
+    }
+}
@@ -1,14 +1,63 @@
-use std::{str::FromStr, time::Duration};
-
-use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::{
+    num::NonZeroUsize,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use lru::LruCache;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    StatusCode,
+};
 use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
 use url::Url;
 
+mod error;
+#[cfg(feature = "geo")]
+mod geo;
+mod geometry;
 mod ident;
-
+#[cfg(feature = "offline")]
+mod offline;
+mod position;
+mod search_params;
+
+pub use error::Error;
+#[cfg(feature = "geo")]
+pub use geo::{NoGeometryError, Places};
+pub use geometry::GeometryFormat;
 pub use ident::IdentificationMethod;
+#[cfg(feature = "offline")]
+pub use offline::OfflineCell;
+pub use position::Position;
+pub use search_params::{FeatureType, Layer, SearchParams, Viewbox};
+
+#[cfg(feature = "offline")]
+use offline::OfflineDb;
+
+/// The default minimum interval between requests, matching the one request per second
+/// limit imposed by the public `nominatim.openstreetmap.org` server.
+///
+/// [Usage policy](https://operations.osmfoundation.org/policies/nominatim/)
+pub const DEFAULT_MIN_REQUEST_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The key [`Client::reverse`] results are cached under: a [`Position`] alone isn't
+/// enough, since `zoom` and the effective [`GeometryFormat`] both change the server's
+/// response for the same coordinate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ReverseCacheKey {
+    position: Position,
+    zoom: Option<u8>,
+    geometry_format: GeometryFormat,
+}
 
 /// The interface for accessing a Nominatim API server.
+///
+/// Cloning a [`Client`] is cheap and shares the rate-limit clock and caches with the
+/// original, so cloning out to fan requests across multiple tasks still obeys a single
+/// rate limit and reuses a single cache, rather than each clone enforcing its own.
 #[derive(Debug, Clone)]
 pub struct Client {
     ident: IdentificationMethod, // how to access the server
@@ -16,6 +65,14 @@ pub struct Client {
     client: reqwest::Client,
     /// HTTP Request Timeout [`Duration`]
     pub timeout: Duration,
+    min_interval: Duration,
+    last_request: Arc<Mutex<Option<Instant>>>,
+    geometry_format: GeometryFormat,
+    cache_capacity: Option<NonZeroUsize>,
+    reverse_cache: Option<Arc<Mutex<LruCache<ReverseCacheKey, Place>>>>,
+    search_cache: Option<Arc<Mutex<LruCache<String, Vec<Place>>>>>,
+    #[cfg(feature = "offline")]
+    offline: Option<Arc<OfflineDb>>,
 }
 
 impl Client {
@@ -31,6 +88,14 @@ impl Client {
                 .build()
                 .unwrap(),
             timeout,
+            min_interval: DEFAULT_MIN_REQUEST_INTERVAL,
+            last_request: Arc::new(Mutex::new(None)),
+            geometry_format: GeometryFormat::None,
+            cache_capacity: None,
+            reverse_cache: None,
+            search_cache: None,
+            #[cfg(feature = "offline")]
+            offline: None,
         }
     }
 
@@ -41,61 +106,192 @@ impl Client {
         Ok(())
     }
 
-    /// Check the status of the nominatim server.
+    /// Enable an in-memory LRU cache of the given capacity for [`Client::reverse`] and
+    /// [`Client::search`] results, keyed by [`Position`] (together with `zoom` and the
+    /// effective [`GeometryFormat`]) and by the normalized query string respectively.
+    /// Disabled by default.
+    pub fn set_cache_capacity(&mut self, capacity: NonZeroUsize) {
+        self.cache_capacity = Some(capacity);
+        self.reverse_cache = Some(Arc::new(Mutex::new(LruCache::new(capacity))));
+        self.search_cache = Some(Arc::new(Mutex::new(LruCache::new(capacity))));
+    }
+
+    /// Set the default [`GeometryFormat`] used by every place-returning call.
     ///
-    /// [Documentation](https://nominatim.org/release-docs/develop/api/Status/)
-    pub async fn status(&self) -> Result<Status, reqwest::Error> {
-        let mut url = self.base_url.join("status.php").unwrap();
-        url.set_query(Some("format=json"));
+    /// Defaults to [`GeometryFormat::None`]. Overridable per search via
+    /// [`SearchParams::geometry_format`].
+    pub fn set_geometry_format(&mut self, geometry_format: GeometryFormat) {
+        self.geometry_format = geometry_format;
+    }
+
+    /// Configure an offline database of [`OfflineCell`]s for [`Client::reverse_offline`],
+    /// and as an automatic fallback for [`Client::reverse`] when the HTTP request
+    /// times out or the connection fails.
+    #[cfg(feature = "offline")]
+    pub fn with_offline_db(&mut self, path: impl AsRef<std::path::Path>) -> Result<(), Error> {
+        self.offline = Some(Arc::new(OfflineDb::open(path)?));
+
+        Ok(())
+    }
 
+    /// Set the minimum interval enforced between requests.
+    ///
+    /// Defaults to [`DEFAULT_MIN_REQUEST_INTERVAL`] (one second), matching the usage
+    /// policy of the public `nominatim.openstreetmap.org` server. Set this lower if
+    /// you run your own instance without that restriction.
+    pub fn set_rate_limit(&mut self, min_interval: Duration) {
+        self.min_interval = min_interval;
+    }
+
+    /// Build the headers identifying this client to the server, as required by the
+    /// [usage policy](https://operations.osmfoundation.org/policies/nominatim/).
+    fn auth_headers(&self) -> Result<HeaderMap, Error> {
         let mut headers = HeaderMap::new();
         headers.append(
-            HeaderName::from_str(&self.ident.header()).expect("invalid nominatim auth header name"),
+            HeaderName::from_str(&self.ident.header())
+                .map_err(|_| Error::InvalidHeader(self.ident.header()))?,
             HeaderValue::from_str(&self.ident.value())
-                .expect("invalid nominatim auth header value"),
+                .map_err(|_| Error::InvalidHeader(self.ident.value()))?,
         );
 
-        self.client
-            .get(url)
-            .headers(headers)
-            .timeout(self.timeout)
-            .send()
-            .await?
-            .json()
-            .await
+        Ok(headers)
     }
 
-    /// Get [`Place`]s from a search query.
+    /// Wait until enough time has passed since the last request, then record this one.
+    async fn throttle(&self) {
+        let mut last_request = self.last_request.lock().await;
+
+        if let Some(last_request) = *last_request {
+            let elapsed = last_request.elapsed();
+            if elapsed < self.min_interval {
+                tokio::time::sleep(self.min_interval - elapsed).await;
+            }
+        }
+
+        *last_request = Some(Instant::now());
+    }
+
+    /// Send a request, honoring the configured rate limit and retrying once if the
+    /// server responds with `429 Too Many Requests` and a `Retry-After` header.
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, Error> {
+        let request = request.build()?;
+
+        self.throttle().await;
+        let response = self.client.execute(request.try_clone().unwrap()).await?;
+
+        if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse::<u64>().ok())
+                .map(Duration::from_secs);
+
+            let Some(retry_after) = retry_after else {
+                return Err(Error::RateLimited {
+                    retry_after: Duration::ZERO,
+                });
+            };
+
+            tokio::time::sleep(retry_after).await;
+            self.throttle().await;
+            let retried = self.client.execute(request).await?;
+
+            if retried.status() == StatusCode::TOO_MANY_REQUESTS {
+                return Err(Error::RateLimited { retry_after });
+            }
+
+            return Ok(retried);
+        }
+
+        Ok(response)
+    }
+
+    /// Check the status of the nominatim server.
+    ///
+    /// [Documentation](https://nominatim.org/release-docs/develop/api/Status/)
+    pub async fn status(&self) -> Result<Status, Error> {
+        let mut url = self.base_url.join("status.php").unwrap();
+        url.set_query(Some("format=json"));
+
+        let headers = self.auth_headers()?;
+
+        self.send(
+            self.client
+                .get(url)
+                .headers(headers)
+                .timeout(self.timeout),
+        )
+        .await?
+        .json()
+        .await
+        .map_err(Error::Json)
+    }
+
+    /// Get [`Place`]s from a search query, with no optional [`SearchParams`] fields set
+    /// (no language restriction, no result limit, and so on) and the client's default
+    /// [`GeometryFormat`].
     ///
     /// [Documentation](https://nominatim.org/release-docs/develop/api/Search/)
-    pub async fn search(&self, query: impl AsRef<str>) -> Result<Vec<Place>, reqwest::Error> {
+    pub async fn search(&self, query: impl AsRef<str>) -> Result<Vec<Place>, Error> {
+        self.search_with(&SearchParams::new(query.as_ref())).await
+    }
+
+    /// Get [`Place`]s from a [`SearchParams`] builder, covering the full set of
+    /// documented Nominatim search options.
+    ///
+    /// Returns [`Error::EmptyResults`] if the server had no matches.
+    ///
+    /// [Documentation](https://nominatim.org/release-docs/develop/api/Search/)
+    pub async fn search_with(&self, params: &SearchParams) -> Result<Vec<Place>, Error> {
+        let cache_key = params.cache_key(self.geometry_format);
+
+        if let Some(cache) = &self.search_cache {
+            if let Some(places) = cache.lock().await.get(&cache_key) {
+                return Ok(places.clone());
+            }
+        }
+
         let mut url = self.base_url.clone();
-        url.set_query(Some(&format!(
-            "addressdetails=1&extratags=1&q={}&format=json&polygon_geojson=1",
-            query.as_ref().replace(' ', "+")
-        )));
+        url.set_query(Some(&params.query_string(self.geometry_format)));
 
-        let mut headers = HeaderMap::new();
-        headers.append(
-            HeaderName::from_str(&self.ident.header()).expect("invalid nominatim auth header name"),
-            HeaderValue::from_str(&self.ident.value())
-                .expect("invalid nominatim auth header value"),
-        );
+        let mut headers = self.auth_headers()?;
+
+        if let Some(accept_language) = &params.accept_language {
+            headers.append(
+                HeaderName::from_static("accept-language"),
+                HeaderValue::from_str(accept_language)
+                    .map_err(|_| Error::InvalidHeader(accept_language.clone()))?,
+            );
+        }
 
-        self.client
-            .get(url)
-            .headers(headers)
-            .timeout(self.timeout)
-            .send()
+        let places: Vec<Place> = self
+            .send(
+                self.client
+                    .get(url)
+                    .headers(headers)
+                    .timeout(self.timeout),
+            )
             .await?
             .json()
             .await
+            .map_err(Error::Json)?;
+
+        if places.is_empty() {
+            return Err(Error::EmptyResults);
+        }
+
+        if let Some(cache) = &self.search_cache {
+            cache.lock().await.put(cache_key, places.clone());
+        }
+
+        Ok(places)
     }
 
     pub async fn search_structured(
         &self,
         params: &SearchStructuredParams,
-    ) -> Result<Vec<Place>, reqwest::Error> {
+    ) -> Result<Vec<Place>, Error> {
         let mut url = self.base_url.clone();
 
         // Build the query string with structured parameters
@@ -116,100 +312,170 @@ impl Client {
         query_parts.push("format=json".to_string());
         query_parts.push("addressdetails=true".to_string());
         query_parts.push("extratags=true".to_string());
-        query_parts.push("polygon_geojson=true".to_string());
+
+        if let Some(geometry_param) = self.geometry_format.query_param() {
+            query_parts.push(geometry_param.to_string());
+        }
 
         url.set_query(Some(&query_parts.join("&")));
 
-        let mut headers = HeaderMap::new();
-        headers.append(
-            HeaderName::from_str(&self.ident.header()).expect("invalid nominatim auth header name"),
-            HeaderValue::from_str(&self.ident.value())
-                .expect("invalid nominatim auth header value"),
-        );
+        let headers = self.auth_headers()?;
 
-        self.client
-            .get(url)
-            .headers(headers)
-            .timeout(self.timeout)
-            .send()
+        let places: Vec<Place> = self
+            .send(
+                self.client
+                    .get(url)
+                    .headers(headers)
+                    .timeout(self.timeout),
+            )
             .await?
             .json()
             .await
+            .map_err(Error::Json)?;
+
+        if places.is_empty() {
+            return Err(Error::EmptyResults);
+        }
+
+        Ok(places)
     }
 
-    /// Generate a [`Place`] from latitude and longitude.
+    /// Generate a [`Place`] from a [`Position`].
+    ///
+    /// If the `offline` feature is enabled and an offline database was configured via
+    /// [`Client::with_offline_db`], a timed-out or unreachable HTTP request falls back
+    /// to [`Client::reverse_offline`] instead of failing outright.
     ///
     /// [Documentation](https://nominatim.org/release-docs/develop/api/Reverse/)
-    pub async fn reverse(
-        &self,
-        latitude: impl AsRef<str>,
-        longitude: impl AsRef<str>,
-        zoom: Option<u8>,
-    ) -> Result<Place, reqwest::Error> {
+    pub async fn reverse(&self, position: Position, zoom: Option<u8>) -> Result<Place, Error> {
+        let result = self.reverse_http(position, zoom).await;
+
+        if let Err(err) = &result {
+            if let Some(fallback) = self.offline_fallback(err, position) {
+                return fallback;
+            }
+        }
+
+        result
+    }
+
+    #[cfg(feature = "offline")]
+    fn offline_fallback(&self, err: &Error, position: Position) -> Option<Result<Place, Error>> {
+        let offline = self.offline.as_ref()?;
+        let is_unreachable = matches!(err, Error::Http(err) if err.is_timeout() || err.is_connect());
+
+        is_unreachable.then(|| offline.lookup(position))
+    }
+
+    #[cfg(not(feature = "offline"))]
+    fn offline_fallback(&self, _err: &Error, _position: Position) -> Option<Result<Place, Error>> {
+        None
+    }
+
+    /// Resolve a [`Position`] to a coarse [`Place`] (country/state/city) from the
+    /// offline database configured via [`Client::with_offline_db`], without any
+    /// network call.
+    #[cfg(feature = "offline")]
+    pub async fn reverse_offline(&self, position: Position) -> Result<Place, Error> {
+        let offline = self
+            .offline
+            .as_ref()
+            .ok_or_else(|| Error::Offline("no offline database configured".to_string()))?;
+
+        offline.lookup(position)
+    }
+
+    async fn reverse_http(&self, position: Position, zoom: Option<u8>) -> Result<Place, Error> {
+        let cache_key = ReverseCacheKey {
+            position,
+            zoom,
+            geometry_format: self.geometry_format,
+        };
+
+        if let Some(cache) = &self.reverse_cache {
+            if let Some(place) = cache.lock().await.get(&cache_key) {
+                return Ok(place.clone());
+            }
+        }
+
         let mut url = self.base_url.join("reverse").unwrap();
+        let (lat, lon) = position.format(Position::HASH_PRECISION);
+        let geometry_param = self
+            .geometry_format
+            .query_param()
+            .map(|param| format!("&{param}"))
+            .unwrap_or_default();
 
         match zoom {
             Some(zoom) => {
                 url.set_query(Some(&format!(
-                    "addressdetails=1&extratags=1&format=json&lat={}&lon={}&zoom={}",
-                    latitude.as_ref().replace(' ', ""),
-                    longitude.as_ref().replace(' ', ""),
-                    zoom
+                    "addressdetails=1&extratags=1&format=json&lat={lat}&lon={lon}&zoom={zoom}{geometry_param}",
                 )));
             }
             None => {
                 url.set_query(Some(&format!(
-                    "addressdetails=1&extratags=1&format=json&lat={}&lon={}",
-                    latitude.as_ref().replace(' ', ""),
-                    longitude.as_ref().replace(' ', ""),
+                    "addressdetails=1&extratags=1&format=json&lat={lat}&lon={lon}{geometry_param}",
                 )));
             }
         }
 
-        let mut headers = HeaderMap::new();
-        headers.append(
-            HeaderName::from_str(&self.ident.header()).expect("invalid nominatim auth header name"),
-            HeaderValue::from_str(&self.ident.value())
-                .expect("invalid nominatim auth header value"),
-        );
+        let headers = self.auth_headers()?;
 
-        self.client
-            .get(url)
-            .headers(headers)
-            .timeout(self.timeout)
-            .send()
+        let place: Place = self
+            .send(
+                self.client
+                    .get(url)
+                    .headers(headers)
+                    .timeout(self.timeout),
+            )
             .await?
             .json()
             .await
+            .map_err(Error::Json)?;
+
+        if let Some(cache) = &self.reverse_cache {
+            cache.lock().await.put(cache_key, place.clone());
+        }
+
+        Ok(place)
     }
 
     /// Return [`Place`]s from a list of OSM Node, Way, or Relations.
     ///
     /// [Documentation](https://nominatim.org/release-docs/develop/api/Lookup/)
-    pub async fn lookup(&self, queries: Vec<&str>) -> Result<Vec<Place>, reqwest::Error> {
+    pub async fn lookup(&self, queries: Vec<&str>) -> Result<Vec<Place>, Error> {
         let queries = queries.join(",");
 
+        let geometry_param = self
+            .geometry_format
+            .query_param()
+            .map(|param| format!("&{param}"))
+            .unwrap_or_default();
+
         let mut url = self.base_url.join("lookup").unwrap();
         url.set_query(Some(&format!(
-            "osm_ids={}&addressdetails=1&extratags=1&format=json",
-            queries
+            "osm_ids={queries}&addressdetails=1&extratags=1&format=json{geometry_param}",
         )));
 
-        let mut headers = HeaderMap::new();
-        headers.append(
-            HeaderName::from_str(&self.ident.header()).expect("invalid nominatim auth header name"),
-            HeaderValue::from_str(&self.ident.value())
-                .expect("invalid nominatim auth header value"),
-        );
+        let headers = self.auth_headers()?;
 
-        self.client
-            .get(url)
-            .headers(headers)
-            .timeout(self.timeout)
-            .send()
+        let places: Vec<Place> = self
+            .send(
+                self.client
+                    .get(url)
+                    .headers(headers)
+                    .timeout(self.timeout),
+            )
             .await?
             .json()
             .await
+            .map_err(Error::Json)?;
+
+        if places.is_empty() {
+            return Err(Error::EmptyResults);
+        }
+
+        Ok(places)
     }
 }
 
@@ -283,6 +549,12 @@ pub struct Place {
     pub address: Option<Address>,
     pub extratags: Option<ExtraTags>,
     pub geojson: Option<GeoJson>,
+    /// Present when the request used [`GeometryFormat::Kml`].
+    pub geokml: Option<String>,
+    /// Present when the request used [`GeometryFormat::Svg`].
+    pub geosvg: Option<String>,
+    /// Present when the request used [`GeometryFormat::Text`].
+    pub geotext: Option<String>,
     pub name: Option<String>,
 }
 
@@ -0,0 +1,104 @@
+//! An optional, network-free reverse-geocoding fallback backed by a local database of
+//! bounding-box cells, gated behind the `offline` feature.
+//!
+//! `maxminddb`-style databases are keyed by IP address via longest-prefix CIDR
+//! matching, which has no natural correspondence to a latitude/longitude coordinate,
+//! so this module rolls its own tiny format instead: a JSON array of [`OfflineCell`]s,
+//! each a bounding box with the place data to return for any [`Position`] that falls
+//! inside it. When several cells contain the same position, [`OfflineDb::lookup`]
+//! picks the one with the smallest area, on the assumption that it's the most
+//! specific match.
+//!
+//! Parsing that JSON pulls in `serde_json` as a dependency of the `offline` feature,
+//! on top of `serde` itself.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Address, Error, Place, Position};
+
+/// A single entry in an offline database: a bounding box and the place data to return
+/// for any [`Position`] that falls inside it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfflineCell {
+    pub min_lat: f64,
+    pub max_lat: f64,
+    pub min_lon: f64,
+    pub max_lon: f64,
+    pub country: Option<String>,
+    pub country_code: Option<String>,
+    pub state: Option<String>,
+    pub city: Option<String>,
+}
+
+impl OfflineCell {
+    fn contains(&self, position: &Position) -> bool {
+        (self.min_lat..=self.max_lat).contains(&position.lat)
+            && (self.min_lon..=self.max_lon).contains(&position.lon)
+    }
+
+    fn area(&self) -> f64 {
+        (self.max_lat - self.min_lat) * (self.max_lon - self.min_lon)
+    }
+}
+
+/// A coordinate-indexed offline database, loaded from a JSON file of [`OfflineCell`]s.
+#[derive(Debug)]
+pub(crate) struct OfflineDb {
+    cells: Vec<OfflineCell>,
+}
+
+impl OfflineDb {
+    pub(crate) fn open(path: impl AsRef<std::path::Path>) -> Result<Self, Error> {
+        let bytes = std::fs::read(path).map_err(|err| Error::Offline(format!("failed to open: {err}")))?;
+        let cells: Vec<OfflineCell> =
+            serde_json::from_slice(&bytes).map_err(|err| Error::Offline(format!("failed to parse: {err}")))?;
+
+        Ok(Self { cells })
+    }
+
+    pub(crate) fn lookup(&self, position: Position) -> Result<Place, Error> {
+        let cell = self
+            .cells
+            .iter()
+            .filter(|cell| cell.contains(&position))
+            .min_by(|a, b| a.area().total_cmp(&b.area()))
+            .ok_or_else(|| Error::Offline("no offline database entry covers this position".to_string()))?;
+
+        let display_name = [&cell.city, &cell.state, &cell.country]
+            .into_iter()
+            .flatten()
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        Ok(Place {
+            place_id: 0,
+            licence: String::new(),
+            osm_type: String::new(),
+            osm_id: 0,
+            boundingbox: Vec::new(),
+            lat: position.lat.to_string(),
+            lon: position.lon.to_string(),
+            display_name,
+            class: None,
+            _type: None,
+            importance: None,
+            icon: None,
+            address: Some(Address {
+                city: cell.city.clone(),
+                state_district: None,
+                state: cell.state.clone(),
+                iso3166_2_lvl4: None,
+                postcode: None,
+                country: cell.country.clone(),
+                country_code: cell.country_code.clone(),
+            }),
+            extratags: None,
+            geojson: None,
+            geokml: None,
+            geosvg: None,
+            geotext: None,
+            name: None,
+        })
+    }
+}
@@ -0,0 +1,113 @@
+use std::{
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+/// A latitude/longitude pair passed to [`Client::reverse`](crate::Client::reverse).
+///
+/// Equality and hashing quantize both coordinates to [`Position::HASH_PRECISION`]
+/// decimal places, so two positions that only differ below that precision are
+/// treated as the same key. This is what makes [`Position`] usable as a cache key
+/// for repeated lookups of effectively-the-same coordinate.
+#[derive(Debug, Clone, Copy)]
+pub struct Position {
+    pub lat: f64,
+    pub lon: f64,
+}
+
+impl Position {
+    /// The number of decimal places used when comparing and hashing positions.
+    pub const HASH_PRECISION: usize = 6;
+
+    /// Create a new [`Position`] from a latitude and longitude.
+    pub fn new(lat: f64, lon: f64) -> Self {
+        Self { lat, lon }
+    }
+
+    /// Format this position's coordinates at the given decimal precision, suitable
+    /// for use as `lat`/`lon` query parameters.
+    pub fn format(&self, precision: usize) -> (String, String) {
+        (
+            format!("{:.precision$}", self.lat, precision = precision),
+            format!("{:.precision$}", self.lon, precision = precision),
+        )
+    }
+
+    fn cache_key(&self) -> (String, String) {
+        self.format(Self::HASH_PRECISION)
+    }
+}
+
+impl PartialEq for Position {
+    fn eq(&self, other: &Self) -> bool {
+        self.cache_key() == other.cache_key()
+    }
+}
+
+impl Eq for Position {}
+
+impl Hash for Position {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.cache_key().hash(state);
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (lat, lon) = self.format(Self::HASH_PRECISION);
+        write!(f, "{lat},{lon}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_rounds_to_the_given_precision() {
+        let position = Position::new(52.123456789, 13.987654321);
+
+        assert_eq!(
+            position.format(4),
+            ("52.1235".to_string(), "13.9877".to_string())
+        );
+    }
+
+    #[test]
+    fn equality_ignores_differences_below_hash_precision() {
+        let a = Position::new(52.123_456_1, 13.123_456_1);
+        let b = Position::new(52.123_456_4, 13.123_456_4);
+
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn equality_detects_differences_at_hash_precision() {
+        let a = Position::new(52.123_456, 13.123_456);
+        let b = Position::new(52.123_457, 13.123_456);
+
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn equal_positions_hash_the_same() {
+        use std::collections::hash_map::DefaultHasher;
+
+        let a = Position::new(52.123_456_1, 13.123_456_1);
+        let b = Position::new(52.123_456_4, 13.123_456_4);
+
+        let mut hasher_a = DefaultHasher::new();
+        a.hash(&mut hasher_a);
+        let mut hasher_b = DefaultHasher::new();
+        b.hash(&mut hasher_b);
+
+        assert_eq!(hasher_a.finish(), hasher_b.finish());
+    }
+
+    #[test]
+    fn display_matches_hash_precision_format() {
+        let position = Position::new(52.123_456_789, 13.123_456_789);
+
+        assert_eq!(position.to_string(), "52.123457,13.123457");
+    }
+}
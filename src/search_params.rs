@@ -0,0 +1,271 @@
+use crate::GeometryFormat;
+
+/// A single layer to restrict [`SearchParams::layer`] results to.
+///
+/// [Documentation](https://nominatim.org/release-docs/develop/api/Search/#result-restriction)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layer {
+    Address,
+    Poi,
+    Railway,
+    Natural,
+    Manmade,
+}
+
+impl Layer {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Layer::Address => "address",
+            Layer::Poi => "poi",
+            Layer::Railway => "railway",
+            Layer::Natural => "natural",
+            Layer::Manmade => "manmade",
+        }
+    }
+}
+
+/// A feature type to restrict [`SearchParams::feature_type`] results to.
+///
+/// [Documentation](https://nominatim.org/release-docs/develop/api/Search/#result-restriction)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeatureType {
+    Country,
+    State,
+    City,
+    Settlement,
+}
+
+impl FeatureType {
+    fn as_str(&self) -> &'static str {
+        match self {
+            FeatureType::Country => "country",
+            FeatureType::State => "state",
+            FeatureType::City => "city",
+            FeatureType::Settlement => "settlement",
+        }
+    }
+}
+
+/// A bounding box used by [`SearchParams::viewbox`], as `(left, top, right, bottom)`.
+pub type Viewbox = (f64, f64, f64, f64);
+
+/// A builder for the parameters accepted by the Nominatim [Search
+/// endpoint](https://nominatim.org/release-docs/develop/api/Search/).
+///
+/// Construct one with [`SearchParams::new`], chain in the knobs you need, and pass it
+/// to [`Client::search_with`](crate::Client::search_with). [`Client::search`](crate::Client::search) is a thin
+/// wrapper around a [`SearchParams`] with no optional fields set.
+#[derive(Debug, Clone, Default)]
+pub struct SearchParams {
+    pub(crate) query: String,
+    pub(crate) accept_language: Option<String>,
+    pub(crate) limit: Option<u32>,
+    pub(crate) countrycodes: Option<Vec<String>>,
+    pub(crate) viewbox: Option<Viewbox>,
+    pub(crate) bounded: Option<bool>,
+    pub(crate) dedupe: Option<bool>,
+    pub(crate) layer: Option<Vec<Layer>>,
+    pub(crate) feature_type: Option<FeatureType>,
+    pub(crate) exclude_place_ids: Option<Vec<usize>>,
+    pub(crate) geometry_format: Option<GeometryFormat>,
+}
+
+impl SearchParams {
+    /// Create a new [`SearchParams`] for the given free-form query, with no optional
+    /// fields set.
+    pub fn new(query: impl Into<String>) -> Self {
+        Self {
+            query: query.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Request results in a given language, via the `Accept-Language` header.
+    pub fn accept_language(mut self, accept_language: impl Into<String>) -> Self {
+        self.accept_language = Some(accept_language.into());
+        self
+    }
+
+    /// Limit the number of returned results.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Restrict results to one or more ISO 3166-1alpha2 country codes.
+    pub fn countrycodes(mut self, countrycodes: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.countrycodes = Some(countrycodes.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Prefer (or, combined with [`SearchParams::bounded`], restrict to) results
+    /// within the given `(left, top, right, bottom)` bounding box.
+    pub fn viewbox(mut self, viewbox: Viewbox) -> Self {
+        self.viewbox = Some(viewbox);
+        self
+    }
+
+    /// When set with [`SearchParams::viewbox`], exclude results outside the box
+    /// instead of merely preferring results inside it.
+    pub fn bounded(mut self, bounded: bool) -> Self {
+        self.bounded = Some(bounded);
+        self
+    }
+
+    /// Deduplicate results that appear to describe the same place.
+    pub fn dedupe(mut self, dedupe: bool) -> Self {
+        self.dedupe = Some(dedupe);
+        self
+    }
+
+    /// Restrict results to the given layers.
+    pub fn layer(mut self, layer: impl IntoIterator<Item = Layer>) -> Self {
+        self.layer = Some(layer.into_iter().collect());
+        self
+    }
+
+    /// Restrict results to the given feature type.
+    pub fn feature_type(mut self, feature_type: FeatureType) -> Self {
+        self.feature_type = Some(feature_type);
+        self
+    }
+
+    /// Exclude the given place ids from the results, useful for paging through
+    /// repeated searches.
+    pub fn exclude_place_ids(mut self, exclude_place_ids: impl IntoIterator<Item = usize>) -> Self {
+        self.exclude_place_ids = Some(exclude_place_ids.into_iter().collect());
+        self
+    }
+
+    /// Override the [`Client`](crate::Client)'s default [`GeometryFormat`] for this
+    /// search.
+    pub fn geometry_format(mut self, geometry_format: GeometryFormat) -> Self {
+        self.geometry_format = Some(geometry_format);
+        self
+    }
+
+    /// Serialize the set fields (other than [`SearchParams::accept_language`], which is
+    /// sent as a header) into a Nominatim query string, falling back to
+    /// `default_geometry_format` when [`SearchParams::geometry_format`] wasn't set.
+    pub(crate) fn query_string(&self, default_geometry_format: GeometryFormat) -> String {
+        let mut query_parts = vec![
+            "addressdetails=1".to_string(),
+            "extratags=1".to_string(),
+            "format=json".to_string(),
+            format!("q={}", urlencoding::encode(&self.query)),
+        ];
+
+        if let Some(geometry_param) = self
+            .geometry_format
+            .unwrap_or(default_geometry_format)
+            .query_param()
+        {
+            query_parts.push(geometry_param.to_string());
+        }
+
+        if let Some(limit) = self.limit {
+            query_parts.push(format!("limit={limit}"));
+        }
+
+        if let Some(countrycodes) = &self.countrycodes {
+            let codes: Vec<String> = countrycodes
+                .iter()
+                .map(|code| urlencoding::encode(code).into_owned())
+                .collect();
+            query_parts.push(format!("countrycodes={}", codes.join(",")));
+        }
+
+        if let Some((left, top, right, bottom)) = self.viewbox {
+            query_parts.push(format!("viewbox={left},{top},{right},{bottom}"));
+        }
+
+        if let Some(bounded) = self.bounded {
+            query_parts.push(format!("bounded={}", bounded as u8));
+        }
+
+        if let Some(dedupe) = self.dedupe {
+            query_parts.push(format!("dedupe={}", dedupe as u8));
+        }
+
+        if let Some(layer) = &self.layer {
+            let layers: Vec<&str> = layer.iter().map(Layer::as_str).collect();
+            query_parts.push(format!("layer={}", layers.join(",")));
+        }
+
+        if let Some(feature_type) = self.feature_type {
+            query_parts.push(format!("featureType={}", feature_type.as_str()));
+        }
+
+        if let Some(exclude_place_ids) = &self.exclude_place_ids {
+            let ids: Vec<String> = exclude_place_ids.iter().map(ToString::to_string).collect();
+            query_parts.push(format!("exclude_place_ids={}", ids.join(",")));
+        }
+
+        query_parts.join("&")
+    }
+
+    /// A cache key that, unlike [`SearchParams::query_string`], also accounts for
+    /// [`SearchParams::accept_language`] since that field is carried by a header
+    /// rather than the query string.
+    pub(crate) fn cache_key(&self, default_geometry_format: GeometryFormat) -> String {
+        match &self.accept_language {
+            Some(accept_language) => {
+                format!("{}#{accept_language}", self.query_string(default_geometry_format))
+            }
+            None => self.query_string(default_geometry_format),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn query_string_percent_encodes_special_characters() {
+        let params = SearchParams::new("AT&T Tower, #5");
+
+        let query = params.query_string(GeometryFormat::None);
+
+        assert!(query.contains("q=AT%26T%20Tower%2C%20%235"));
+    }
+
+    #[test]
+    fn query_string_joins_countrycodes_with_unencoded_commas() {
+        let params = SearchParams::new("test").countrycodes(["us", "gb"]);
+
+        let query = params.query_string(GeometryFormat::None);
+
+        assert!(query.contains("countrycodes=us,gb"));
+    }
+
+    #[test]
+    fn query_string_includes_set_fields() {
+        let params = SearchParams::new("berlin")
+            .limit(5)
+            .bounded(true)
+            .dedupe(false)
+            .layer([Layer::Address, Layer::Poi])
+            .feature_type(FeatureType::City)
+            .exclude_place_ids([1, 2, 3]);
+
+        let query = params.query_string(GeometryFormat::None);
+
+        assert!(query.contains("limit=5"));
+        assert!(query.contains("bounded=1"));
+        assert!(query.contains("dedupe=0"));
+        assert!(query.contains("layer=address,poi"));
+        assert!(query.contains("featureType=city"));
+        assert!(query.contains("exclude_place_ids=1,2,3"));
+    }
+
+    #[test]
+    fn cache_key_appends_accept_language_but_query_string_does_not() {
+        let params = SearchParams::new("berlin").accept_language("de");
+
+        let query_string = params.query_string(GeometryFormat::None);
+        let cache_key = params.cache_key(GeometryFormat::None);
+
+        assert_eq!(cache_key, format!("{query_string}#de"));
+    }
+}